@@ -2,16 +2,23 @@ use std::str::FromStr;
 
 use anyhow::Result;
 use evdev::KeyCode;
-use midi::MidiAction;
+use midi::{MidiAction, channel_from_u8};
+use rel::RelAction;
 
 use crate::MidiKeyMapping;
 
 pub mod midi;
+pub mod rel;
 
 pub struct Action {
     pub desc: String,
     pub keys: Vec<KeyCode>,
     pub midi: Vec<MidiAction>,
+    pub rel: Vec<RelAction>,
+    pub hold: bool,
+    pub value_min: u8,
+    pub value_max: u8,
+    pub channel: Option<u8>,
 }
 
 impl TryFrom<&MidiKeyMapping> for Action {
@@ -40,10 +47,34 @@ impl TryFrom<&MidiKeyMapping> for Action {
             None => vec![],
         };
 
+        let rel = match &value.rel {
+            Some(rel) => rel
+                .iter()
+                .map(|r| match RelAction::try_from(r.as_str()) {
+                    Ok(r) => Ok(r),
+                    Err(_) => anyhow::bail!("Invalid Rel Action: {:?}", r),
+                })
+                .collect::<Result<Vec<RelAction>>>()?,
+            None => vec![],
+        };
+
+        let channel = match value.channel {
+            Some(c) => {
+                channel_from_u8(c)?;
+                Some(c)
+            }
+            None => None,
+        };
+
         Ok(Self {
             desc: String::from(&value.description),
             keys,
             midi,
+            rel,
+            hold: value.hold,
+            value_min: value.value_min.unwrap_or(0),
+            value_max: value.value_max.unwrap_or(127),
+            channel,
         })
     }
 }
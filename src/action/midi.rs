@@ -1,15 +1,26 @@
 use anyhow::Result;
 use itertools::Itertools;
-use midi_msg::{Channel, ChannelVoiceMsg, ControlChange, MidiMsg};
+use midi_msg::{Channel, ChannelVoiceMsg, ControlChange, MidiMsg, SystemExclusiveMsg};
+
+use crate::katana;
+
+#[derive(Debug)]
+enum Payload {
+    Msg(MidiMsg),
+    Raw(Vec<u8>),
+}
 
 #[derive(Debug)]
 pub struct MidiAction {
-    msg: MidiMsg,
+    payload: Payload,
 }
 
 impl MidiAction {
     pub fn to_midi(&self) -> Vec<u8> {
-        self.msg.to_midi()
+        match &self.payload {
+            Payload::Msg(m) => m.to_midi(),
+            Payload::Raw(bytes) => bytes.clone(),
+        }
     }
 }
 
@@ -24,20 +35,64 @@ impl TryFrom<&str> for MidiAction {
             }
         };
 
-        let msg = match type_ {
-            "CC" => parse_cc_action(val),
-            "PC" => parse_pc_action(val),
-            _ => anyhow::bail!("Invalid action: {}", value),
+        let payload = match type_ {
+            "SYSEX" => parse_sysex_action(val).map(Payload::Raw),
+            "KATANA" => parse_katana_action(val).map(Payload::Msg),
+            _ => {
+                let (channel, val) = parse_channel(val)?;
+
+                match type_ {
+                    "CC" => parse_cc_action(channel, val),
+                    "PC" => parse_pc_action(channel, val),
+                    "NOTE" => parse_note_action(channel, val),
+                    "PITCH" => parse_pitch_action(channel, val),
+                    _ => anyhow::bail!("Invalid action: {}", value),
+                }
+                .map(Payload::Msg)
+            }
         };
 
-        match msg {
-            Ok(m) => Ok(MidiAction { msg: m }),
+        match payload {
+            Ok(p) => Ok(MidiAction { payload: p }),
             Err(e) => Err(anyhow::anyhow!(format!("Unable to parse {}: {}", value, e))),
         }
     }
 }
 
-fn parse_cc_action(value: &str) -> Result<MidiMsg> {
+fn parse_channel(value: &str) -> Result<(Channel, &str)> {
+    match value.split_once(' ') {
+        Some((c, rest)) if c.chars().all(|c| c.is_ascii_digit()) => {
+            Ok((channel_from_u8(c.parse::<u8>()?)?, rest))
+        }
+        _ => Ok((Channel::Ch1, value)),
+    }
+}
+
+pub fn channel_from_u8(channel: u8) -> Result<Channel> {
+    let ch = match channel {
+        1 => Channel::Ch1,
+        2 => Channel::Ch2,
+        3 => Channel::Ch3,
+        4 => Channel::Ch4,
+        5 => Channel::Ch5,
+        6 => Channel::Ch6,
+        7 => Channel::Ch7,
+        8 => Channel::Ch8,
+        9 => Channel::Ch9,
+        10 => Channel::Ch10,
+        11 => Channel::Ch11,
+        12 => Channel::Ch12,
+        13 => Channel::Ch13,
+        14 => Channel::Ch14,
+        15 => Channel::Ch15,
+        16 => Channel::Ch16,
+        _ => anyhow::bail!("Invalid channel: {}", channel),
+    };
+
+    Ok(ch)
+}
+
+fn parse_cc_action(channel: Channel, value: &str) -> Result<MidiMsg> {
     let parts: Vec<u8> = value
         .splitn(2, ":")
         .map(|v| match v.parse::<u8>() {
@@ -56,16 +111,74 @@ fn parse_cc_action(value: &str) -> Result<MidiMsg> {
     };
 
     Ok(MidiMsg::ChannelVoice {
-        channel: Channel::Ch1,
+        channel,
         msg: ChannelVoiceMsg::ControlChange { control: cc },
     })
 }
 
-fn parse_pc_action(value: &str) -> Result<MidiMsg> {
+fn parse_pc_action(channel: Channel, value: &str) -> Result<MidiMsg> {
     let p = value.parse::<u8>()?;
 
     Ok(MidiMsg::ChannelVoice {
-        channel: Channel::Ch1,
+        channel,
         msg: ChannelVoiceMsg::ProgramChange { program: p },
     })
 }
+
+fn parse_note_action(channel: Channel, value: &str) -> Result<MidiMsg> {
+    let parts: Vec<u8> = value
+        .splitn(2, ":")
+        .map(|v| match v.parse::<u8>() {
+            Ok(i) => Ok(i),
+            Err(e) => Err(anyhow::anyhow!(e)),
+        })
+        .collect::<Result<Vec<u8>>>()?;
+
+    if parts.len() != 2 {
+        anyhow::bail!("value should contain exactly 2 parts")
+    }
+
+    let (note, velocity) = (parts[0], parts[1]);
+
+    let msg = if velocity > 0 {
+        ChannelVoiceMsg::NoteOn { note, velocity }
+    } else {
+        ChannelVoiceMsg::NoteOff { note, velocity }
+    };
+
+    Ok(MidiMsg::ChannelVoice { channel, msg })
+}
+
+fn parse_pitch_action(channel: Channel, value: &str) -> Result<MidiMsg> {
+    let bend = value.parse::<u16>()?;
+
+    Ok(MidiMsg::ChannelVoice {
+        channel,
+        msg: ChannelVoiceMsg::PitchBend { bend },
+    })
+}
+
+fn parse_sysex_action(value: &str) -> Result<Vec<u8>> {
+    parse_hex_bytes(value)
+}
+
+fn parse_katana_action(value: &str) -> Result<MidiMsg> {
+    let data = parse_hex_bytes(value)?;
+    let payload = katana::create_sysex_payload(&data);
+
+    Ok(MidiMsg::SystemExclusive {
+        msg: SystemExclusiveMsg::Commercial {
+            id: katana::ROLAND_MANUFACTURER_ID,
+            data: payload,
+        },
+    })
+}
+
+fn parse_hex_bytes(value: &str) -> Result<Vec<u8>> {
+    value
+        .split_whitespace()
+        .map(|b| {
+            u8::from_str_radix(b, 16).map_err(|e| anyhow::anyhow!("Invalid byte {}: {}", b, e))
+        })
+        .collect::<Result<Vec<u8>>>()
+}
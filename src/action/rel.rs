@@ -0,0 +1,36 @@
+use anyhow::Result;
+use evdev::RelativeAxisCode;
+use itertools::Itertools;
+
+#[derive(Debug)]
+pub struct RelAction {
+    pub axis: RelativeAxisCode,
+    pub factor: i32,
+}
+
+impl TryFrom<&str> for RelAction {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (axis, factor) = match value.splitn(2, " ").collect_tuple() {
+            Some((a, f)) => (a, f),
+            None => {
+                anyhow::bail!(format!("Unable to parse {}", value))
+            }
+        };
+
+        let axis = match axis {
+            "REL_X" => RelativeAxisCode::REL_X,
+            "REL_Y" => RelativeAxisCode::REL_Y,
+            "REL_WHEEL" => RelativeAxisCode::REL_WHEEL,
+            "REL_HWHEEL" => RelativeAxisCode::REL_HWHEEL,
+            _ => anyhow::bail!("Invalid axis: {}", axis),
+        };
+
+        let factor = factor
+            .parse::<i32>()
+            .map_err(|e| anyhow::anyhow!("Invalid factor {}: {}", factor, e))?;
+
+        Ok(RelAction { axis, factor })
+    }
+}
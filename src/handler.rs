@@ -2,26 +2,34 @@ use std::collections::HashMap;
 
 use anyhow::Result;
 use evdev::uinput::VirtualDevice;
-use evdev::{InputEvent, KeyCode, KeyEvent};
+use evdev::{EventType, InputEvent, KeyCode, KeyEvent};
 use log::{debug, warn};
-use midi_msg::{Channel, ChannelVoiceMsg, ControlChange, MidiMsg};
 use midir::MidiOutputConnection;
 use midly::MidiMessage;
 use midly::live::LiveEvent;
 
 use crate::action::midi::MidiAction;
+use crate::action::rel::RelAction;
 use crate::midi::MidiMessageHandler;
 use crate::{Action, Event};
 
 pub struct Handler {
     pub kb: VirtualDevice,
     pub midi_out: Option<MidiOutputConnection>,
-    pub mappings: HashMap<Event, Action>,
+    pub mappings: HashMap<(Event, Option<u8>), Action>,
+    pub active_notes: HashMap<(Event, u8), Vec<KeyCode>>,
+    pub forward: bool,
 }
 
 impl MidiMessageHandler for Handler {
     fn handle(&mut self, _: u64, raw_message: &[u8]) -> Result<()> {
-        let (_ch, msg) = match LiveEvent::parse(raw_message) {
+        if self.forward {
+            if let Some(midi_out) = self.midi_out.as_mut() {
+                midi_out.send(raw_message)?;
+            }
+        }
+
+        let (ch, msg) = match LiveEvent::parse(raw_message) {
             Ok(LiveEvent::Midi { channel, message }) => (channel, message),
             Ok(evt) => {
                 warn!("Ignoring non Midi event: {:?}", evt);
@@ -32,13 +40,18 @@ impl MidiMessageHandler for Handler {
             }
         };
 
-        let evt = match msg {
-            MidiMessage::ProgramChange { program: p } => Some(Event::PC(p.as_int())),
+        let (evt, note_off, cc_value) = match msg {
+            MidiMessage::ProgramChange { program: p } => (Some(Event::PC(p.as_int())), false, None),
             MidiMessage::Controller {
                 controller: c,
-                value: _,
-            } => Some(Event::CC(c.as_int())),
-            _ => None,
+                value: v,
+            } => (Some(Event::CC(c.as_int())), false, Some(v.as_int())),
+            MidiMessage::NoteOn { key, vel } if vel.as_int() > 0 => {
+                (Some(Event::Note(key.as_int())), false, None)
+            }
+            MidiMessage::NoteOn { key, vel: _ } => (Some(Event::Note(key.as_int())), true, None),
+            MidiMessage::NoteOff { key, vel: _ } => (Some(Event::Note(key.as_int())), true, None),
+            _ => (None, false, None),
         };
 
         if evt.is_none() {
@@ -46,30 +59,94 @@ impl MidiMessageHandler for Handler {
             return Ok(());
         }
         let evt = evt.unwrap();
+        let cfg_channel = ch.as_int() + 1;
+
+        if note_off {
+            if let Some(keys) = self.active_notes.remove(&(evt, cfg_channel)) {
+                emit_key_release(&mut self.kb, &keys)?;
+            }
 
-        let act = self.mappings.get(&evt);
+            return Ok(());
+        }
+
+        let act = self
+            .mappings
+            .get(&(evt, Some(cfg_channel)))
+            .or_else(|| self.mappings.get(&(evt, None)));
         if act.is_none() {
             warn!("Unsupported message: {:?}", msg);
             return Ok(());
         }
 
         let act = act.unwrap();
+
+        if let Some(v) = cc_value {
+            if v < act.value_min || v > act.value_max {
+                return Ok(());
+            }
+        }
+
         debug!("{}", act.desc);
 
-        emit_keyboard_events(&mut self.kb, &act.keys)?;
+        if act.hold && matches!(evt, Event::Note(_)) {
+            emit_key_press(&mut self.kb, &act.keys)?;
+            self.active_notes.insert((evt, cfg_channel), act.keys.clone());
+        } else {
+            emit_keyboard_events(&mut self.kb, &act.keys)?;
+        }
+
+        if let Some(v) = cc_value {
+            emit_rel_events(&mut self.kb, &act.rel, v)?;
+        }
+
         emit_midi_events(&mut self.midi_out, &act.midi)?;
 
         Ok(())
     }
 }
 
+impl Drop for Handler {
+    fn drop(&mut self) {
+        for keys in self.active_notes.values() {
+            if let Err(e) = emit_key_release(&mut self.kb, keys) {
+                warn!("Error releasing held keys: {:?}", e);
+            }
+        }
+    }
+}
+
+fn emit_key_press(kb: &mut VirtualDevice, keys: &[KeyCode]) -> Result<()> {
+    let evts: Vec<InputEvent> = keys.iter().map(|k| *KeyEvent::new(*k, 1)).collect();
+
+    kb.emit(&evts)?;
+
+    Ok(())
+}
+
+fn emit_key_release(kb: &mut VirtualDevice, keys: &[KeyCode]) -> Result<()> {
+    let evts: Vec<InputEvent> = keys.iter().rev().map(|k| *KeyEvent::new(*k, 0)).collect();
+
+    kb.emit(&evts)?;
+
+    Ok(())
+}
+
 fn emit_keyboard_events(kb: &mut VirtualDevice, keys: &[KeyCode]) -> Result<()> {
-    let mut evts: Vec<InputEvent> = keys.iter().map(|k| *KeyEvent::new(*k, 1)).collect();
+    emit_key_press(kb, keys)?;
+    emit_key_release(kb, keys)?;
+
+    Ok(())
+}
 
-    keys.iter().rev().for_each(|k| {
-        let e = *KeyEvent::new(*k, 0);
-        evts.push(e);
-    });
+fn emit_rel_events(kb: &mut VirtualDevice, acts: &[RelAction], value: u8) -> Result<()> {
+    if acts.is_empty() {
+        return Ok(());
+    }
+
+    let evts: Vec<InputEvent> = acts
+        .iter()
+        .map(|a| InputEvent::new(EventType::RELATIVE.0, a.axis.0, (value as i32 - 64) * a.factor))
+        .collect();
 
     kb.emit(&evts)?;
 
@@ -92,24 +169,7 @@ fn emit_midi_events(
     };
 
     for act in acts {
-        let midi_msg = match *act {
-            MidiAction::PC(p) => MidiMsg::ChannelVoice {
-                channel: Channel::Ch1,
-                msg: ChannelVoiceMsg::ProgramChange { program: p },
-            },
-            MidiAction::CC(c, v) => {
-                let cc = ControlChange::CC {
-                    control: c,
-                    value: v,
-                };
-                MidiMsg::ChannelVoice {
-                    channel: Channel::Ch1,
-                    msg: ChannelVoiceMsg::ControlChange { control: cc },
-                }
-            }
-        };
-
-        midi_out.send(&midi_msg.to_midi())?;
+        midi_out.send(&act.to_midi())?;
     }
 
     Ok(())
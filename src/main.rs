@@ -2,10 +2,12 @@ use std::collections::HashMap;
 use std::fs::read_to_string;
 use std::path::PathBuf;
 use std::sync::mpsc::channel;
+use std::time::Duration;
 
+use action::midi::channel_from_u8;
 use action::Action;
 use anyhow::Result;
-use log::info;
+use log::{debug, info, warn};
 use serde::Deserialize;
 
 use crate::handler::Handler;
@@ -13,15 +15,18 @@ use crate::midi::get_midi_output_conn;
 
 mod action;
 mod handler;
+mod katana;
 mod midi;
 mod virtual_keyboard;
 
 const APP_NAME: &str = "midi2key";
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
 
-#[derive(Debug, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 enum Event {
     PC(u8),
     CC(u8),
+    Note(u8),
 }
 
 impl TryFrom<&str> for Event {
@@ -32,11 +37,18 @@ impl TryFrom<&str> for Event {
             .split_once(' ')
             .ok_or_else(|| anyhow::anyhow!("Invalid event: {}", value))?;
 
-        let val = v.parse::<u8>()?;
-
         let evt = match t {
-            "PC" => Event::PC(val),
-            "CC" => Event::CC(val),
+            "PC" => Event::PC(v.parse::<u8>()?),
+            "CC" => Event::CC(v.parse::<u8>()?),
+            // "NOTE <n>" or "NOTE <channel> <n>"
+            "NOTE" => {
+                let note = match v.split_once(' ') {
+                    Some((_ch, n)) => n.parse::<u8>()?,
+                    None => v.parse::<u8>()?,
+                };
+
+                Event::Note(note)
+            }
             _ => anyhow::bail!("Invalid event type: {}: {}", t, value),
         };
 
@@ -44,10 +56,23 @@ impl TryFrom<&str> for Event {
     }
 }
 
+fn event_channel(event: &str) -> Option<u8> {
+    let (t, v) = event.split_once(' ')?;
+    if t != "NOTE" {
+        return None;
+    }
+
+    let (ch, _n) = v.split_once(' ')?;
+    ch.parse::<u8>().ok()
+}
+
 #[derive(Debug, Deserialize)]
 struct Config {
     midi_input: String,
     midi_output: Option<String>,
+    channel: Option<u8>,
+    #[serde(default)]
+    forward: bool,
     mappings: Vec<MidiKeyMapping>,
 }
 
@@ -57,6 +82,12 @@ struct MidiKeyMapping {
     description: String,
     keys: Option<Vec<String>>,
     midi: Option<Vec<String>>,
+    rel: Option<Vec<String>>,
+    #[serde(default)]
+    hold: bool,
+    value_min: Option<u8>,
+    value_max: Option<u8>,
+    channel: Option<u8>,
 }
 
 fn get_config() -> Result<Config> {
@@ -74,16 +105,34 @@ fn get_config() -> Result<Config> {
     Ok(config)
 }
 
-fn get_mappings(config: &Config) -> Result<HashMap<Event, Action>> {
+fn get_mappings(config: &Config) -> Result<HashMap<(Event, Option<u8>), Action>> {
     let mut mappings = HashMap::new();
 
     for m in &config.mappings {
         info!("Adding mapping: {:?} => {}", m.event, m.description);
 
         let event = m.event.as_str().try_into()?;
-        let action = (m).try_into()?;
-
-        mappings.insert(event, action);
+        let mut action: Action = (m).try_into()?;
+        if action.channel.is_none() {
+            if let Some(c) = event_channel(&m.event) {
+                channel_from_u8(c)?;
+                action.channel = Some(c);
+            } else {
+                action.channel = config.channel;
+            }
+        }
+
+        let key = (event, action.channel);
+        if mappings.contains_key(&key) {
+            anyhow::bail!(
+                "Duplicate mapping for event {:?} on channel {:?}: {}",
+                event,
+                action.channel,
+                m.description
+            );
+        }
+
+        mappings.insert(key, action);
     }
 
     Ok(mappings)
@@ -95,6 +144,10 @@ fn main() -> Result<()> {
         .parse_default_env()
         .init();
 
+    if std::env::args().nth(1).as_deref() == Some("--list") {
+        return midi::list_ports();
+    }
+
     let config = get_config()?;
 
     let kb = virtual_keyboard::create_virtual_keyboard()?;
@@ -109,15 +162,50 @@ fn main() -> Result<()> {
         kb,
         midi_out,
         mappings,
+        active_notes: HashMap::new(),
+        forward: config.forward,
     };
-    let _conn = midi::get_midi_input_conn(&config.midi_input, handler)?;
 
     let (tx, rx) = channel();
     ctrlc::set_handler(move || tx.send(()).expect("Could not send signal on channel."))
         .expect("Error setting Ctrl-C handler");
     info!("Running. Press Ctrl-C to quit.");
 
-    rx.recv().expect("Could not receive from channel.");
+    let mut handler = Some(handler);
+    let mut conn = None;
+
+    loop {
+        if conn.is_none() {
+            if midi::is_input_port_available(&config.midi_input).unwrap_or(false) {
+                if let Some(h) = handler.take() {
+                    match midi::get_midi_input_conn(&config.midi_input, h) {
+                        Ok(c) => {
+                            info!("Connected to {}", config.midi_input);
+                            conn = Some(c);
+                        }
+                        Err(e) => warn!("Failed to connect to {}: {:?}", config.midi_input, e),
+                    }
+                }
+            } else {
+                debug!("Device {} not found, waiting...", config.midi_input);
+            }
+        }
+
+        if rx.recv_timeout(RECONNECT_INTERVAL).is_ok() {
+            break;
+        }
+
+        if let Some(c) = conn.take() {
+            if midi::is_input_port_available(&config.midi_input).unwrap_or(false) {
+                conn = Some(c);
+            } else {
+                warn!("Device {} disconnected", config.midi_input);
+                let (_, h) = c.close();
+                handler = Some(h);
+            }
+        }
+    }
+
     info!("Closing connection");
 
     Ok(())
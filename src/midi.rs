@@ -14,10 +14,10 @@ pub trait MidiMessageHandler {
     fn handle(&mut self, stamp: u64, data: &[u8]) -> Result<()>;
 }
 
-pub fn get_midi_input_conn(
+pub fn get_midi_input_conn<H: MidiMessageHandler + Send + 'static>(
     device_name: &str,
-    mut handler: impl MidiMessageHandler + Send + 'static,
-) -> Result<MidiInputConnection<()>> {
+    handler: H,
+) -> Result<MidiInputConnection<H>> {
     let mut midi_in = MidiInput::new("midir reading input")?;
     midi_in.ignore(Ignore::None);
 
@@ -29,12 +29,12 @@ pub fn get_midi_input_conn(
     let conn_res = midi_in.connect(
         &port,
         "midi2key-read-input",
-        move |s, m, _| {
+        |s, m, handler| {
             if let Err(e) = handler.handle(s, m) {
                 error!("Error handling midi message: {:?}", e);
             };
         },
-        (),
+        handler,
     );
 
     match conn_res {
@@ -43,6 +43,32 @@ pub fn get_midi_input_conn(
     }
 }
 
+pub fn list_ports() -> Result<()> {
+    let midi_in = MidiInput::new("midir reading input")?;
+    println!("Input ports:");
+    for (i, p) in midi_in.ports().iter().enumerate() {
+        if let Ok(name) = midi_in.port_name(p) {
+            println!("  {}: {}", i, name);
+        }
+    }
+
+    let midi_out = MidiOutput::new("midir output")?;
+    println!("Output ports:");
+    for (i, p) in midi_out.ports().iter().enumerate() {
+        if let Ok(name) = midi_out.port_name(p) {
+            println!("  {}: {}", i, name);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn is_input_port_available(device_name: &str) -> Result<bool> {
+    let midi_in = MidiInput::new("midir reading input")?;
+
+    Ok(get_midi_port(&midi_in, device_name).is_ok())
+}
+
 pub fn get_midi_output_conn(device_name: &str) -> Result<MidiOutputConnection> {
     let midi_out = MidiOutput::new("midir output")?;
 